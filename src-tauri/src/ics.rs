@@ -0,0 +1,120 @@
+// RFC 5545 (iCalendar) export of the prayer schedule, so users can subscribe
+// to it from their system calendar and get reminders the calendar itself
+// delivers via VALARM, independent of the app running.
+
+use chrono::{ Datelike, Offset, Utc };
+use chrono_tz::Tz;
+
+use crate::PrayerTime;
+
+fn rfc5545_offset(offset_seconds: i32) -> String {
+    let sign = if offset_seconds < 0 { '-' } else { '+' };
+    let total_minutes = offset_seconds.abs() / 60;
+    format!("{}{:02}{:02}", sign, total_minutes / 60, total_minutes % 60)
+}
+
+fn fold_line(line: &str) -> String {
+    // RFC 5545 requires folding lines longer than 75 octets; none of ours
+    // get close to that, so this returns the line unchanged and folds nothing.
+    line.to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(',', "\\,").replace(';', "\\;")
+}
+
+// Builds the VTIMEZONE block covering every offset the exported range
+// actually observes, instead of snapshotting a single day's offset — so
+// calendar apps resolve the right UTC instant on both sides of a DST
+// transition that falls inside the export. One observance is emitted per
+// offset change, in chronological order.
+fn build_vtimezone(tzid: &str, tz: Tz, prayer_days: &[Vec<PrayerTime>]) -> Vec<String> {
+    let mut observances: Vec<(chrono::NaiveDateTime, i32, i32)> = Vec::new();
+    let mut prev_offset: Option<i32> = None;
+
+    for day in prayer_days {
+        let Some(first) = day.first() else {
+            continue;
+        };
+        let local = first.datetime.with_timezone(&tz);
+        let offset = local.offset().fix().local_minus_utc();
+
+        if prev_offset != Some(offset) {
+            let offset_from = prev_offset.unwrap_or(offset);
+            observances.push((local.date_naive().and_hms_opt(0, 0, 0).unwrap(), offset_from, offset));
+            prev_offset = Some(offset);
+        }
+    }
+
+    if observances.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec!["BEGIN:VTIMEZONE".to_string(), format!("TZID:{}", tzid)];
+    for (dtstart, offset_from, offset_to) in &observances {
+        let kind = if offset_to > offset_from { "DAYLIGHT" } else { "STANDARD" };
+        lines.push(format!("BEGIN:{}", kind));
+        lines.push(format!("DTSTART:{}", dtstart.format("%Y%m%dT%H%M%S")));
+        lines.push(format!("TZOFFSETFROM:{}", rfc5545_offset(*offset_from)));
+        lines.push(format!("TZOFFSETTO:{}", rfc5545_offset(*offset_to)));
+        lines.push(format!("END:{}", kind));
+    }
+    lines.push("END:VTIMEZONE".to_string());
+    lines
+}
+
+/// Builds an RFC 5545 iCalendar document covering every day in
+/// `prayer_days`, one VEVENT per prayer in `enabled_prayers` with a VALARM
+/// firing `alarm_lead_minutes` before it.
+pub fn build_ics(
+    tz: Tz,
+    prayer_days: &[Vec<PrayerTime>],
+    enabled_prayers: &[String],
+    alarm_lead_minutes: i64
+) -> String {
+    let tzid = tz.name();
+    let now_stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Lets Pray//Prayer Schedule//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string()
+    ];
+
+    lines.extend(build_vtimezone(tzid, tz, prayer_days));
+
+    for day in prayer_days {
+        for prayer in day.iter().filter(|p| enabled_prayers.contains(&p.name)) {
+            let local = prayer.datetime.with_timezone(&tz);
+            let dtstart = local.format("%Y%m%dT%H%M%S").to_string();
+            let uid = format!(
+                "{}-{}{:02}{:02}@letspray",
+                prayer.name.to_lowercase(),
+                local.year(),
+                local.month(),
+                local.day()
+            );
+
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", uid));
+            lines.push(format!("DTSTAMP:{}", now_stamp));
+            lines.push(format!("DTSTART;TZID={}:{}", tzid, dtstart));
+            lines.push(format!("SUMMARY:{}", escape_text(&prayer.name)));
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{} prayer time", escape_text(&prayer.name)));
+            lines.push(format!("TRIGGER:-PT{}M", alarm_lead_minutes));
+            lines.push("END:VALARM".to_string());
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n") + "\r\n"
+}
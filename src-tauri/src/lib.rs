@@ -1,13 +1,26 @@
-use chrono::{ DateTime, Local, NaiveTime, TimeZone };
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+    LocalResult,
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    Offset,
+    TimeZone,
+    Utc,
+};
 use chrono_tz::Tz;
 use std::str::FromStr;
 use reqwest;
 use serde::{ Deserialize, Serialize };
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::Duration as StdDuration;
 use tauri::{ AppHandle, Emitter, WindowEvent, Manager };
 use std::sync::Mutex;
+#[cfg(target_os = "windows")]
 use std::ffi::OsString;
+#[cfg(target_os = "windows")]
 use std::os::windows::ffi::OsStringExt;
 #[cfg(target_os = "windows")]
 use winapi::um::timezoneapi::{ GetTimeZoneInformation, TIME_ZONE_INFORMATION };
@@ -22,15 +35,54 @@ use tauri::{
     State,
 };
 
+mod windows_zones;
+use windows_zones::windows_zone_to_iana;
+
+mod solar;
+use solar::{ AsrJuristic, CalculationMethod };
+
+mod ics;
+
 // Global state for settings
 type AppState = Mutex<AppSettings>;
 
+// Reminder subsystem state: which prayers have already notified today (so a
+// tick that straddles a snooze doesn't double-fire) and which prayers have a
+// pending snoozed re-fire.
+#[derive(Debug, Default)]
+struct ReminderState {
+    fired_today: HashMap<String, chrono::NaiveDate>,
+    snoozed: HashMap<String, (DateTime<Local>, PrayerTime)>,
+}
+type ReminderStateHandle = Mutex<ReminderState>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SystemInfo {
     pub timezone: String,
     pub location: String,
 }
 
+/// One entry in the full IANA zone list `list_timezones` returns, for a
+/// searchable, offset-annotated timezone picker.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimezoneEntry {
+    /// Canonical IANA zone id, e.g. "Asia/Jakarta". This is what gets stored
+    /// in `AppSettings.location` when the user picks a zone directly.
+    pub id: String,
+    /// Current UTC offset, e.g. "+07:00".
+    pub offset: String,
+    /// Region grouping for the picker, e.g. "Asia".
+    pub region: String,
+    /// Human-readable label, e.g. "Jakarta (+07:00)".
+    pub label: String,
+}
+
+fn format_utc_offset(offset_seconds: i32) -> String {
+    let sign = if offset_seconds < 0 { '-' } else { '+' };
+    let total_minutes = offset_seconds.abs() / 60;
+    format!("{}{:02}:{:02}", sign, total_minutes / 60, total_minutes % 60)
+}
+
 // Helper function to convert Windows wide string to Rust string
 #[cfg(target_os = "windows")]
 fn wide_string_to_string(wide_str: &[u16]) -> String {
@@ -106,6 +158,32 @@ fn get_windows_location() -> Result<String, String> {
     Err("Windows location detection not available on this platform".to_string())
 }
 
+// Read the IANA zone the OS itself is configured with, so the rest of the
+// app can work from a single canonical `Tz` regardless of platform.
+#[cfg(target_os = "windows")]
+fn get_system_timezone() -> Option<Tz> {
+    let windows_id = get_windows_timezone().ok()?;
+    windows_zone_to_iana(&windows_id)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn get_system_timezone() -> Option<Tz> {
+    // The `TZ` env var takes precedence when set, matching glibc/libc behavior.
+    if let Ok(tz_env) = std::env::var("TZ") {
+        if let Ok(tz) = Tz::from_str(&tz_env) {
+            return Some(tz);
+        }
+    }
+
+    // Otherwise /etc/localtime is conventionally a symlink into the system
+    // zoneinfo database, e.g. /usr/share/zoneinfo/Asia/Jakarta.
+    let link = std::fs::read_link("/etc/localtime").ok()?;
+    let link_str = link.to_string_lossy();
+    let zoneinfo_marker = "zoneinfo/";
+    let zone_id = link_str.split(zoneinfo_marker).nth(1)?;
+    Tz::from_str(zone_id).ok()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PrayerTime {
     pub name: String,
@@ -133,15 +211,26 @@ pub struct PrayerDate {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppSettings {
     pub location: String,
+    /// Coordinates for `location`. `list_timezones` lets the frontend store
+    /// any of the ~400 IANA zone ids as `location`, and those ids alone don't
+    /// carry a position, so the frontend must submit the coordinates it
+    /// picked (e.g. from a map or city search) alongside the zone.
+    pub latitude: f64,
+    pub longitude: f64,
     pub play_sound: bool,
     pub enabled_prayers: Vec<String>,
     pub run_at_startup: bool,
+    /// Calculation convention used when falling back to the offline
+    /// astronomical calculator (see `solar::calculate_prayer_times`).
+    pub calc_mode: CalculationMethod,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             location: "New Taipei City".to_string(),
+            latitude: 25.033,
+            longitude: 121.5654,
             play_sound: true,
             enabled_prayers: vec![
                 "Fajr".to_string(),
@@ -151,13 +240,16 @@ impl Default for AppSettings {
                 "Isha".to_string()
             ],
             run_at_startup: false,
+            calc_mode: CalculationMethod::default(),
         }
     }
 }
 
 // Helper function to get timezone from location
 fn get_timezone_for_location(location: &str) -> String {
-    // Try to parse location as timezone first
+    // A location picked via `list_timezones` is already a canonical IANA id;
+    // take it as-is instead of falling through to the city-name heuristics
+    // below, which only exist for the legacy free-text location field.
     if let Ok(_tz) = Tz::from_str(location) {
         return location.to_string();
     }
@@ -206,28 +298,58 @@ fn get_timezone_for_location(location: &str) -> String {
             "Australia/Sydney".to_string(),
         location if location.contains("melbourne") => "Australia/Melbourne".to_string(),
         _ => {
-            // Fallback to system timezone
-            let offset_seconds = (*Local::now().offset()).local_minus_utc();
-            match offset_seconds {
-                28800 => "Asia/Taipei".to_string(), // UTC+8
-                25200 => "Asia/Jakarta".to_string(), // UTC+7
-                32400 => "Asia/Tokyo".to_string(), // UTC+9
-                0 => "UTC".to_string(),
-                _ => "UTC".to_string(),
+            // Fallback to the OS's own configured timezone rather than
+            // guessing from a bare UTC offset.
+            get_system_timezone()
+                .map(|tz| tz.name().to_string())
+                .unwrap_or_else(|| "UTC".to_string())
+        }
+    }
+}
+
+// Resolves a wall-clock local datetime that may fall in a DST gap or fold,
+// instead of erroring out the way `LocalResult::single()` does.
+pub(crate) fn resolve_local_datetime(tz: &Tz, naive: NaiveDateTime) -> DateTime<Tz> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt,
+        // Fold (e.g. "fall back"): the wall-clock time occurs twice. Pick the
+        // earlier candidate, i.e. the offset still in effect before the
+        // transition (daylight time, for a "fall back").
+        LocalResult::Ambiguous(earlier, _later) => earlier,
+        // Gap (e.g. "spring forward"): the wall-clock time never occurs.
+        // Shift forward until we reach the first valid moment after it.
+        LocalResult::None => {
+            let mut candidate = naive;
+            loop {
+                candidate += Duration::minutes(1);
+                if let LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) =
+                    tz.from_local_datetime(&candidate)
+                {
+                    break dt;
+                }
             }
         }
     }
 }
 
 #[tauri::command]
-async fn fetch_prayer_times(location: String) -> Result<Vec<PrayerTime>, String> {
+async fn fetch_prayer_times(
+    location: String,
+    latitude: f64,
+    longitude: f64
+) -> Result<Vec<PrayerTime>, String> {
     let today = Local::now().format("%d-%m-%Y").to_string();
     let timezone_string = get_timezone_for_location(&location);
 
+    // `location` may be a bare IANA zone id (e.g. "Europe/Helsinki") picked
+    // via `list_timezones` rather than a geocodable address, so resolve
+    // against the coordinates the frontend stored alongside it instead of
+    // Aladhan's address-geocoding endpoint.
     let url = format!(
-        "https://api.aladhan.com/v1/timingsByAddress/{}?address={}&method=3&shafaq=general&tune=5%2C3%2C5%2C7%2C9%2C-1%2C0%2C8%2C-6&timezonestring={}&calendarMethod=UAQ",
+        "https://api.aladhan.com/v1/timings/{}?latitude={}&longitude={}&method=3&shafaq=general&tune=5%2C3%2C5%2C7%2C9%2C-1%2C0%2C8%2C-6&timezonestring={}&calendarMethod=UAQ",
         today,
-        urlencoding::encode(&location),
+        latitude,
+        longitude,
         urlencoding::encode(&timezone_string)
     );
 
@@ -253,12 +375,10 @@ async fn fetch_prayer_times(location: String) -> Result<Vec<PrayerTime>, String>
     for name in &prayer_names {
         if let Some(time_str) = prayer_response.data.timings.get(*name) {
             if let Ok(time) = NaiveTime::parse_from_str(time_str, "%H:%M") {
-                // Create datetime in the correct timezone
+                // Create datetime in the correct timezone, resolving DST
+                // gaps/folds instead of dropping the prayer.
                 let today_naive = Local::now().date_naive();
-                let datetime = tz
-                    .from_local_datetime(&today_naive.and_time(time))
-                    .single()
-                    .ok_or_else(|| format!("Invalid datetime for {}: {}", name, time_str))?
+                let datetime = resolve_local_datetime(&tz, today_naive.and_time(time))
                     .with_timezone(&Local);
 
                 prayer_times.push(PrayerTime {
@@ -273,6 +393,83 @@ async fn fetch_prayer_times(location: String) -> Result<Vec<PrayerTime>, String>
     Ok(prayer_times)
 }
 
+// Computes the day's prayer times from solar geometry instead of calling the
+// Aladhan API, so the app keeps working offline.
+#[tauri::command]
+async fn fetch_prayer_times_offline(
+    location: String,
+    latitude: f64,
+    longitude: f64,
+    calc_mode: CalculationMethod
+) -> Result<Vec<PrayerTime>, String> {
+    let timezone_string = get_timezone_for_location(&location);
+    let tz: Tz = timezone_string
+        .parse()
+        .map_err(|_| format!("Invalid timezone: {}", timezone_string))?;
+
+    solar::calculate_prayer_times(
+        latitude,
+        longitude,
+        Local::now().date_naive(),
+        tz,
+        calc_mode,
+        AsrJuristic::Shafi
+    )
+}
+
+// Exports a whole month of prayer times as an RFC 5545 iCalendar document
+// (with a VALARM per event) for the frontend to save or expose as a file.
+#[tauri::command]
+async fn export_prayer_schedule_ics(
+    location: String,
+    latitude: f64,
+    longitude: f64,
+    calc_mode: CalculationMethod,
+    year: i32,
+    month: u32,
+    alarm_lead_minutes: i64,
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let enabled_prayers = {
+        let settings = state.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings.enabled_prayers.clone()
+    };
+
+    let timezone_string = get_timezone_for_location(&location);
+    let tz: Tz = timezone_string
+        .parse()
+        .map_err(|_| format!("Invalid timezone: {}", timezone_string))?;
+
+    let first_of_month = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(||
+        format!("Invalid year/month: {}-{}", year, month)
+    )?;
+    let next_month_start = (
+        if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
+        }
+    ).ok_or_else(|| "Invalid year/month".to_string())?;
+    let days_in_month = (next_month_start - first_of_month).num_days();
+
+    let mut prayer_days = Vec::new();
+    for day_offset in 0..days_in_month {
+        let date = first_of_month + Duration::days(day_offset);
+        prayer_days.push(
+            solar::calculate_prayer_times(
+                latitude,
+                longitude,
+                date,
+                tz,
+                calc_mode,
+                AsrJuristic::Shafi
+            )?
+        );
+    }
+
+    Ok(ics::build_ics(tz, &prayer_days, &enabled_prayers, alarm_lead_minutes))
+}
+
 #[tauri::command]
 async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
     let settings = state.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
@@ -315,9 +512,92 @@ async fn test_adhan_sound() -> Result<(), String> {
     Ok(())
 }
 
+// Schedules a one-shot re-fire of `name`'s reminder `minutes` from now, and
+// stops the currently-looping adhan.
+#[tauri::command]
+async fn snooze_prayer(
+    name: String,
+    minutes: i64,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    reminder_state: State<'_, ReminderStateHandle>
+) -> Result<(), String> {
+    let _ = app_handle.emit("stop-adhan", ());
+
+    let (location, latitude, longitude) = {
+        let settings = state.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        (settings.location.clone(), settings.latitude, settings.longitude)
+    };
+    let prayer_times = fetch_prayer_times(location, latitude, longitude).await?;
+    let prayer = prayer_times
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Unknown prayer: {}", name))?;
+
+    let fire_at = Local::now() + Duration::minutes(minutes);
+    let mut reminder_state = reminder_state
+        .lock()
+        .map_err(|e| format!("Failed to lock reminder state: {}", e))?;
+    reminder_state.snoozed.insert(name, (fire_at, prayer));
+
+    Ok(())
+}
+
+// Dismisses (or silences) the current reminder by stopping the looping
+// adhan, and cancels any pending snooze for this prayer.
+#[tauri::command]
+async fn dismiss_prayer(
+    name: String,
+    app_handle: AppHandle,
+    reminder_state: State<'_, ReminderStateHandle>
+) -> Result<(), String> {
+    let _ = app_handle.emit("stop-adhan", ());
+
+    let mut reminder_state = reminder_state
+        .lock()
+        .map_err(|e| format!("Failed to lock reminder state: {}", e))?;
+    reminder_state.snoozed.remove(&name);
+
+    Ok(())
+}
+
+// Enumerates the full IANA zone database with each zone's current UTC
+// offset, so the frontend can present a searchable picker and store a
+// canonical `Tz` id in `AppSettings.location` instead of a free-text city.
+fn build_timezone_entries() -> Vec<TimezoneEntry> {
+    let now = Utc::now();
+
+    let mut zones: Vec<TimezoneEntry> = chrono_tz::TZ_VARIANTS.iter()
+        .map(|tz| {
+            let id = tz.name().to_string();
+            let offset_seconds = now.with_timezone(tz).offset().fix().local_minus_utc();
+            let offset = format_utc_offset(offset_seconds);
+            let (region, city) = id.split_once('/').unwrap_or(("Other", id.as_str()));
+
+            TimezoneEntry {
+                id: id.clone(),
+                offset: offset.clone(),
+                region: region.to_string(),
+                label: format!("{} ({})", city.replace('_', " "), offset),
+            }
+        })
+        .collect();
+
+    zones.sort_by(|a, b| a.id.cmp(&b.id));
+
+    zones
+}
+
+#[tauri::command]
+async fn list_timezones() -> Result<Vec<TimezoneEntry>, String> {
+    Ok(build_timezone_entries())
+}
+
 #[tauri::command]
 async fn get_system_info() -> Result<SystemInfo, String> {
-    let timezone = get_windows_timezone().unwrap_or_else(|_| "UTC".to_string());
+    let timezone = get_system_timezone()
+        .map(|tz| tz.name().to_string())
+        .unwrap_or_else(|| "UTC".to_string());
     let location = get_windows_location().unwrap_or_else(|_| "Unknown".to_string());
 
     Ok(SystemInfo {
@@ -385,6 +665,52 @@ fn create_tray_icon(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Erro
 
     Ok(())
 }
+// Shows the main window, emits the actionable `prayer-reminder` event, and
+// starts the adhan sound if enabled. Shared by the on-time path and the
+// snoozed re-fire path so both notify identically.
+fn fire_prayer_reminder(app_handle: &AppHandle, prayer: &PrayerTime, play_sound: bool) {
+    let title = format!("Prayer Time: {}", prayer.name);
+    let body = format!("It's time for {} prayer at {}", prayer.name, prayer.time);
+
+    println!("Prayer time notification: {} at {}", prayer.name, prayer.time);
+
+    // Show the main window
+    if let Some(window) = app_handle.get_webview_window("main") {
+        println!("Showing main window for prayer time: {}", prayer.name);
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        let _ = window.center();
+        // Bring window to front and make it always on top briefly
+        let _ = window.set_always_on_top(true);
+        // Reset always on top after a short delay
+        let window_clone = window.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+            let _ = window_clone.set_always_on_top(false);
+        });
+    } else {
+        println!("Warning: Could not find main window to show");
+    }
+
+    // Send notification event to frontend, with the actions it can offer.
+    let _ = app_handle.emit(
+        "prayer-reminder",
+        serde_json::json!({
+             "title": title,
+             "body": body,
+             "prayer": prayer.name,
+             "actions": ["snooze", "dismiss", "silence"]
+         })
+    );
+
+    // Play adhan sound if enabled
+    if play_sound {
+        // Sound playing will be implemented in the frontend
+        let _ = app_handle.emit("play-adhan", ());
+    }
+}
+
 async fn check_prayer_reminders(app_handle: AppHandle) {
     // Get settings from the global state
     let settings = {
@@ -393,9 +719,55 @@ async fn check_prayer_reminders(app_handle: AppHandle) {
         locked_state.clone()
     };
 
-    match fetch_prayer_times(settings.location).await {
+    // Re-fire any prayers whose snooze has elapsed.
+    let due_snoozes: Vec<PrayerTime> = {
+        let reminder_state: State<ReminderStateHandle> = app_handle.state();
+        let mut reminder_state = reminder_state.lock().unwrap();
+        let now = Local::now();
+        let due_names: Vec<String> = reminder_state.snoozed
+            .iter()
+            .filter(|(_, (fire_at, _))| *fire_at <= now)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        due_names
+            .into_iter()
+            .filter_map(|name| reminder_state.snoozed.remove(&name))
+            .map(|(_, prayer)| {
+                reminder_state.fired_today.insert(prayer.name.clone(), now.date_naive());
+                prayer
+            })
+            .collect()
+    };
+    for prayer in &due_snoozes {
+        fire_prayer_reminder(&app_handle, prayer, settings.play_sound);
+    }
+
+    // Fall back to the offline calculator (with the configured `calc_mode`)
+    // when the Aladhan API can't be reached.
+    let prayer_times = fetch_prayer_times(
+        settings.location.clone(),
+        settings.latitude,
+        settings.longitude
+    ).await.or_else(|e| -> Result<Vec<PrayerTime>, String> {
+        eprintln!("Failed to fetch prayer times, calculating offline: {}", e);
+        let timezone_string = get_timezone_for_location(&settings.location);
+        let tz: Tz = timezone_string
+            .parse()
+            .map_err(|_| format!("Invalid timezone: {}", timezone_string))?;
+        solar::calculate_prayer_times(
+            settings.latitude,
+            settings.longitude,
+            Local::now().date_naive(),
+            tz,
+            settings.calc_mode,
+            AsrJuristic::Shafi
+        )
+    });
+    match prayer_times {
         Ok(prayer_times) => {
             let now = Local::now();
+            let today = now.date_naive();
 
             for prayer in prayer_times {
                 if settings.enabled_prayers.contains(&prayer.name) {
@@ -408,48 +780,22 @@ async fn check_prayer_reminders(app_handle: AppHandle) {
                         time_diff.num_seconds() >= 0 &&
                         time_diff.num_seconds() < 60
                     {
-                        let title = format!("Prayer Time: {}", prayer.name);
-                        let body = format!(
-                            "It's time for {} prayer at {}",
-                            prayer.name,
-                            prayer.time
-                        );
-
-                        println!("Prayer time notification: {} at {}", prayer.name, prayer.time);
-
-                        // Show the main window
-                        if let Some(window) = app_handle.get_webview_window("main") {
-                            println!("Showing main window for prayer time: {}", prayer.name);
-                            let _ = window.show();
-                            let _ = window.unminimize();
-                            let _ = window.set_focus();
-                            let _ = window.center();
-                            // Bring window to front and make it always on top briefly
-                            let _ = window.set_always_on_top(true);
-                            // Reset always on top after a short delay
-                            let window_clone = window.clone();
-                            tauri::async_runtime::spawn(async move {
-                                tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-                                let _ = window_clone.set_always_on_top(false);
-                            });
-                        } else {
-                            println!("Warning: Could not find main window to show");
+                        let already_fired_today = {
+                            let reminder_state: State<ReminderStateHandle> = app_handle.state();
+                            let mut reminder_state = reminder_state.lock().unwrap();
+                            let already_fired =
+                                reminder_state.fired_today.get(&prayer.name) == Some(&today);
+                            if !already_fired {
+                                reminder_state.fired_today.insert(prayer.name.clone(), today);
+                            }
+                            already_fired
+                        };
+
+                        if already_fired_today {
+                            continue;
                         }
 
-                        // Send notification event to frontend
-                        let _ = app_handle.emit(
-                            "prayer-reminder",
-                            serde_json::json!({
-                             "title": title,
-                             "body": body
-                         })
-                        );
-
-                        // Play adhan sound if enabled
-                        if settings.play_sound {
-                            // Sound playing will be implemented in the frontend
-                            let _ = app_handle.emit("play-adhan", ());
-                        }
+                        fire_prayer_reminder(&app_handle, &prayer, settings.play_sound);
                     }
                 }
             }
@@ -462,7 +808,7 @@ async fn check_prayer_reminders(app_handle: AppHandle) {
 
 fn setup_prayer_reminder_timer(app_handle: AppHandle) {
     tauri::async_runtime::spawn(async move {
-        let mut interval = interval(Duration::from_secs(60)); // Check every minute
+        let mut interval = interval(StdDuration::from_secs(60)); // Check every minute
 
         loop {
             interval.tick().await;
@@ -492,6 +838,7 @@ pub fn run() {
             }
         })
         .manage(AppState::new(AppSettings::default()))
+        .manage(ReminderStateHandle::new(ReminderState::default()))
         .setup(|app| {
             let app_handle = app.handle().clone();
             setup_prayer_reminder_timer(app_handle);
@@ -506,13 +853,72 @@ pub fn run() {
         .invoke_handler(
             tauri::generate_handler![
                 fetch_prayer_times,
+                fetch_prayer_times_offline,
+                export_prayer_schedule_ics,
                 get_settings,
                 save_settings,
                 show_notification,
                 test_adhan_sound,
+                snooze_prayer,
+                dismiss_prayer,
+                list_timezones,
                 get_system_info
             ]
         )
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_local_datetime_shifts_forward_past_a_spring_forward_gap() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        // 2024-03-10 02:30 never occurs in America/New_York: clocks jump
+        // from 02:00 straight to 03:00.
+        let naive = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(2, 30, 0).unwrap();
+
+        let resolved = resolve_local_datetime(&tz, naive);
+
+        assert_eq!(resolved.format("%H:%M").to_string(), "03:00");
+    }
+
+    #[test]
+    fn resolve_local_datetime_picks_earlier_candidate_on_a_fall_back_fold() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        // 2024-11-03 01:30 occurs twice: once at EDT (UTC-4), once at EST
+        // (UTC-5). The earlier (EDT) candidate should win.
+        let naive = NaiveDate::from_ymd_opt(2024, 11, 3).unwrap().and_hms_opt(1, 30, 0).unwrap();
+
+        let resolved = resolve_local_datetime(&tz, naive);
+
+        assert_eq!(resolved.offset().fix().local_minus_utc(), -4 * 3600);
+    }
+
+    #[test]
+    fn timezones_from_the_picker_round_trip_as_canonical_ids() {
+        let zones = build_timezone_entries();
+
+        // Pick zones the legacy free-text heuristics would not map back to
+        // themselves ("africa/lagos" even contains "la", which the old rules
+        // send to America/Los_Angeles), so this only passes if
+        // `get_timezone_for_location` is actually taking the canonical id
+        // as-is rather than falling through to the old heuristics.
+        let sample_ids = ["Europe/Helsinki", "Africa/Lagos", "America/Denver"];
+        for id in sample_ids {
+            assert!(zones.iter().any(|z| z.id == id), "list_timezones is missing {}", id);
+            assert_eq!(
+                get_timezone_for_location(id),
+                id,
+                "{} did not round-trip as a canonical zone id",
+                id
+            );
+            // And the result must still parse back into a real `Tz`, the same
+            // way fetch_prayer_times_offline/export_prayer_schedule_ics/
+            // fetch_prayer_times rely on it to build a `DateTime<Tz>`.
+            assert!(Tz::from_str(&get_timezone_for_location(id)).is_ok());
+        }
+    }
+}
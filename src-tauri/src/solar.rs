@@ -0,0 +1,275 @@
+// Offline prayer-time calculation from solar geometry, so the app can work
+// without a network connection. Mirrors the algorithm behind PrayTimes.org
+// (and, by extension, the Aladhan API this app otherwise calls): Julian day
+// -> sun declination/equation-of-time -> hour angles around solar noon.
+
+use chrono::{ Datelike, NaiveDate, NaiveTime, Offset };
+use chrono_tz::Tz;
+use serde::{ Deserialize, Serialize };
+
+use crate::{ resolve_local_datetime, PrayerTime };
+
+/// Angle-based calculation conventions used by prayer-time authorities.
+/// `angles()` gives the sun's depression below the horizon (in degrees) that
+/// defines Fajr and Isha for that convention, as `(fajr, isha)`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CalculationMethod {
+    /// Muslim World League (the method=3 the Aladhan API calls default to).
+    MuslimWorldLeague,
+    Isna,
+    Egyptian,
+    Karachi,
+}
+
+impl CalculationMethod {
+    fn angles(self) -> (f64, f64) {
+        match self {
+            CalculationMethod::MuslimWorldLeague => (18.0, 17.0),
+            CalculationMethod::Isna => (15.0, 15.0),
+            CalculationMethod::Egyptian => (19.5, 17.5),
+            CalculationMethod::Karachi => (18.0, 18.0),
+        }
+    }
+}
+
+impl Default for CalculationMethod {
+    fn default() -> Self {
+        CalculationMethod::MuslimWorldLeague
+    }
+}
+
+/// Asr shadow-length juristic factor: Shafi/Maliki/Hanbali use 1, Hanafi uses 2.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AsrJuristic {
+    Shafi,
+    Hanafi,
+}
+
+impl AsrJuristic {
+    fn shadow_factor(self) -> f64 {
+        match self {
+            AsrJuristic::Shafi => 1.0,
+            AsrJuristic::Hanafi => 2.0,
+        }
+    }
+}
+
+const SUN_ANGLE_SUNRISE_MAGHRIB: f64 = 0.833;
+// Used as a high-latitude fallback when the normal hour-angle equation has
+// no solution (polar day/night): re-derive the angle at this latitude
+// instead, per the common "nearest latitude" rule.
+const NEAREST_LATITUDE_FALLBACK_DEG: f64 = 48.0;
+
+fn julian_day(date: NaiveDate) -> f64 {
+    let year = date.year() as i64;
+    let month = date.month() as i64;
+    let day = date.day() as f64;
+
+    let (y, m) = if month <= 2 { (year - 1, month + 12) } else { (year, month) };
+    let a = (y as f64 / 100.0).floor();
+    let b = 2.0 - a + (a / 4.0).floor();
+
+    (365.25 * ((y as f64) + 4716.0)).floor() +
+        (30.6001 * ((m as f64) + 1.0)).floor() +
+        day +
+        b -
+        1524.5
+}
+
+fn fix_angle(angle: f64) -> f64 {
+    let a = angle % 360.0;
+    if a < 0.0 { a + 360.0 } else { a }
+}
+
+fn fix_hour(hour: f64) -> f64 {
+    let h = hour % 24.0;
+    if h < 0.0 { h + 24.0 } else { h }
+}
+
+/// Declination (degrees) and equation of time (hours) of the sun on `date`.
+fn sun_position(date: NaiveDate) -> (f64, f64) {
+    let d = julian_day(date) - 2451545.0;
+    let g = fix_angle(357.529 + 0.98560028 * d).to_radians();
+    let q = fix_angle(280.459 + 0.98564736 * d);
+    let l = fix_angle(q + 1.915 * g.sin() + 0.02 * (2.0 * g).sin()).to_radians();
+    let e = (23.439 - 0.00000036 * d).to_radians();
+
+    let right_ascension = (e.cos() * l.sin()).atan2(l.cos()).to_degrees() / 15.0;
+    let equation_of_time = q / 15.0 - fix_hour(right_ascension);
+    let declination = (e.sin() * l.sin()).asin().to_degrees();
+
+    (declination, equation_of_time)
+}
+
+/// Hour angle (in hours from solar noon) at which the sun reaches `alpha_deg`
+/// below the horizon. `None` when there is no solution (e.g. polar summer).
+fn hour_angle(latitude_deg: f64, declination_deg: f64, alpha_deg: f64) -> Option<f64> {
+    let lat = latitude_deg.to_radians();
+    let decl = declination_deg.to_radians();
+    let alpha = alpha_deg.to_radians();
+
+    let arg = (-alpha.sin() - lat.sin() * decl.sin()) / (lat.cos() * decl.cos());
+    if !(-1.0..=1.0).contains(&arg) {
+        return None;
+    }
+    Some(arg.acos().to_degrees() / 15.0)
+}
+
+fn asr_hour_angle(latitude_deg: f64, declination_deg: f64, shadow_factor: f64) -> Option<f64> {
+    let lat = latitude_deg.to_radians();
+    let decl = declination_deg.to_radians();
+
+    let shadow_angle = (shadow_factor + (lat - decl).abs().tan()).recip().atan();
+    let arg = (shadow_angle.sin() - lat.sin() * decl.sin()) / (lat.cos() * decl.cos());
+    if !(-1.0..=1.0).contains(&arg) {
+        return None;
+    }
+    Some(arg.acos().to_degrees() / 15.0)
+}
+
+/// Evaluates `compute` at `latitude_deg`, retrying at the nearest-latitude
+/// fallback when the high-latitude hour-angle equation has no real solution.
+/// If that has none either, returns 12 hours, placing the event at solar
+/// midnight.
+fn hour_angle_with_fallback(
+    latitude_deg: f64,
+    declination_deg: f64,
+    compute: impl Fn(f64, f64) -> Option<f64>
+) -> f64 {
+    if let Some(hours) = compute(latitude_deg, declination_deg) {
+        return hours;
+    }
+
+    let nearest = NEAREST_LATITUDE_FALLBACK_DEG.copysign(latitude_deg);
+    compute(nearest, declination_deg).unwrap_or(12.0)
+}
+
+fn hour_to_naive_time(hour: f64) -> NaiveTime {
+    let clamped = fix_hour(hour);
+    let total_minutes = (clamped * 60.0).round() as i64;
+    let h = ((total_minutes / 60) % 24) as u32;
+    let m = (total_minutes % 60) as u32;
+    NaiveTime::from_hms_opt(h, m, 0).unwrap_or_else(|| NaiveTime::from_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Computes a full day's prayer times purely from solar geometry, without
+/// calling the Aladhan API. `longitude` is in degrees East-positive.
+pub fn calculate_prayer_times(
+    latitude: f64,
+    longitude: f64,
+    date: NaiveDate,
+    tz: Tz,
+    method: CalculationMethod,
+    asr_juristic: AsrJuristic
+) -> Result<Vec<PrayerTime>, String> {
+    let (declination, equation_of_time) = sun_position(date);
+    let (fajr_angle, isha_angle) = method.angles();
+
+    let utc_offset_hours = {
+        let noon_utc = date.and_hms_opt(12, 0, 0).ok_or("invalid date")?;
+        resolve_local_datetime(&tz, noon_utc).offset().fix().local_minus_utc() as f64 / 3600.0
+    };
+
+    let noon = 12.0 + utc_offset_hours - longitude / 15.0 - equation_of_time;
+
+    let fajr_delta = hour_angle_with_fallback(latitude, declination, |lat, decl|
+        hour_angle(lat, decl, fajr_angle)
+    );
+    let sunrise_delta = hour_angle_with_fallback(latitude, declination, |lat, decl|
+        hour_angle(lat, decl, SUN_ANGLE_SUNRISE_MAGHRIB)
+    );
+    let asr_delta = hour_angle_with_fallback(latitude, declination, |lat, decl|
+        asr_hour_angle(lat, decl, asr_juristic.shadow_factor())
+    );
+    let isha_delta = hour_angle_with_fallback(latitude, declination, |lat, decl|
+        hour_angle(lat, decl, isha_angle)
+    );
+
+    let events = [
+        ("Fajr", noon - fajr_delta),
+        ("Sunrise", noon - sunrise_delta),
+        ("Dhuhr", noon),
+        ("Asr", noon + asr_delta),
+        ("Maghrib", noon + sunrise_delta),
+        ("Isha", noon + isha_delta),
+    ];
+
+    let mut prayer_times = Vec::with_capacity(events.len());
+    for (name, hour) in events {
+        let time = hour_to_naive_time(hour);
+        let datetime = resolve_local_datetime(&tz, date.and_time(time)).with_timezone(&chrono::Local);
+        prayer_times.push(PrayerTime {
+            name: name.to_string(),
+            time: time.format("%H:%M").to_string(),
+            datetime,
+        });
+    }
+
+    Ok(prayer_times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time_to_hour(time: &str) -> f64 {
+        let (h, m) = time.split_once(':').unwrap();
+        h.parse::<f64>().unwrap() + m.parse::<f64>().unwrap() / 60.0
+    }
+
+    #[test]
+    fn jakarta_summer_solstice_times_are_ordered_and_plausible() {
+        let tz: Tz = "Asia/Jakarta".parse().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+
+        let times = calculate_prayer_times(
+            -6.2088,
+            106.8456,
+            date,
+            tz,
+            CalculationMethod::MuslimWorldLeague,
+            AsrJuristic::Shafi
+        ).unwrap();
+
+        let hour_of = |name: &str| {
+            time_to_hour(&times.iter().find(|p| p.name == name).unwrap().time)
+        };
+
+        // Reference: for 106.8456E at UTC+7, the longitude correction alone
+        // puts solar noon at ~11:53 local; the equation of time on this date
+        // shifts it by only another minute or two either way.
+        let dhuhr = hour_of("Dhuhr");
+        assert!((dhuhr - 11.9).abs() < 0.1, "Dhuhr {} not close to ~11:54 local", dhuhr);
+
+        // Every event should occur strictly later than the previous one.
+        let ordered = ["Fajr", "Sunrise", "Dhuhr", "Asr", "Maghrib", "Isha"];
+        let hours: Vec<f64> = ordered.iter().map(|name| hour_of(name)).collect();
+        assert!(hours.windows(2).all(|pair| pair[0] < pair[1]), "times not ordered: {:?}", hours);
+
+        // Sunrise and Maghrib both use the same 0.833 degree angle, so they
+        // should be symmetric around Dhuhr.
+        let sunrise_gap = dhuhr - hour_of("Sunrise");
+        let maghrib_gap = hour_of("Maghrib") - dhuhr;
+        assert!((sunrise_gap - maghrib_gap).abs() < 0.02);
+    }
+
+    #[test]
+    fn high_latitude_falls_back_instead_of_panicking() {
+        let tz: Tz = "Europe/Oslo".parse().unwrap();
+        // Near midsummer, far enough north that the sun never reaches 18
+        // degrees below the horizon: the normal Fajr/Isha equation has no
+        // solution and must fall back rather than panic.
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+
+        let times = calculate_prayer_times(
+            69.6492,
+            18.9553,
+            date,
+            tz,
+            CalculationMethod::MuslimWorldLeague,
+            AsrJuristic::Shafi
+        ).unwrap();
+
+        assert_eq!(times.len(), 6);
+    }
+}
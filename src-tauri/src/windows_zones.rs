@@ -0,0 +1,120 @@
+// Windows <-> IANA (Olson) timezone correspondence, mirroring the mapping
+// maintained by Unicode CLDR in windowsZones.xml. Only the "001" (primary)
+// territory is embedded for each Windows zone ID; that is sufficient to pick
+// a single canonical IANA zone per Windows zone.
+const WINDOWS_TO_IANA: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("US Eastern Standard Time", "America/Indianapolis"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("Argentina Standard Time", "America/Buenos_Aires"),
+    ("Greenland Standard Time", "America/Godthab"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("Namibia Standard Time", "Africa/Windhoek"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kiev"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Russia Time Zone 3", "Europe/Samara"),
+    ("Mauritius Standard Time", "Indian/Mauritius"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Caucasus Standard Time", "Asia/Yerevan"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("India Standard Time", "Asia/Calcutta"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Katmandu"),
+    ("Central Asia Standard Time", "Asia/Almaty"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("Myanmar Standard Time", "Asia/Rangoon"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar"),
+    ("North Asia East Standard Time", "Asia/Irkutsk"),
+    ("Malay Peninsula Standard Time", "Asia/Kuala_Lumpur"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Yakutsk Standard Time", "Asia/Yakutsk"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok"),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal"),
+    ("UTC+12", "Etc/GMT-12"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+];
+
+/// Maps a Windows timezone ID (e.g. "SE Asia Standard Time") to the
+/// canonical IANA zone CLDR's windowsZones table assigns it (e.g.
+/// "Asia/Bangkok"). Returns `None` for unrecognized Windows zone IDs.
+pub fn windows_zone_to_iana(windows_id: &str) -> Option<chrono_tz::Tz> {
+    WINDOWS_TO_IANA
+        .iter()
+        .find(|(windows, _)| *windows == windows_id)
+        .and_then(|(_, iana)| iana.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_known_windows_zones_to_their_iana_equivalent() {
+        assert_eq!(windows_zone_to_iana("SE Asia Standard Time"), Some(chrono_tz::Asia::Bangkok));
+        assert_eq!(windows_zone_to_iana("China Standard Time"), Some(chrono_tz::Asia::Shanghai));
+        assert_eq!(windows_zone_to_iana("Eastern Standard Time"), Some(chrono_tz::America::New_York));
+    }
+
+    #[test]
+    fn returns_none_for_an_unrecognized_windows_zone() {
+        assert_eq!(windows_zone_to_iana("Not A Real Standard Time"), None);
+    }
+}